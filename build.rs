@@ -0,0 +1,17 @@
+//! Generates `src/messages.rs`'s contents from the message/field descriptions in
+//! `build/codegen.rs`, so the typed structs stay in sync with the generator without needing a
+//! manual regeneration step.
+use std::env;
+use std::fs;
+use std::path::Path;
+
+include!("build/codegen.rs");
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set by cargo");
+    let dest = Path::new(&out_dir).join("messages.rs");
+    fs::write(&dest, generate()).expect("failed to write generated messages.rs");
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=build/codegen.rs");
+}
@@ -0,0 +1,194 @@
+// Shared code-generation core for the typed message structs in `crate::messages`.
+//
+// `include!`d verbatim by both `build.rs` (so the generated module is refreshed on every build)
+// and `src/bin/fitgen.rs` (so the exact same generator can be run by hand). Keeping the logic in
+// one file means the two call sites can never drift apart.
+//
+// A plain `//` comment, not `//!`, because this file is spliced into another file's body via
+// `include!` rather than compiled as its own crate root, and inner doc comments are only legal at
+// the very start of one.
+
+struct GeneratedField {
+    rust_name: &'static str,
+    field_number: u8,
+    rust_type: &'static str,
+    value_variant: &'static str,
+    /// Short, field-specific doc line. Required on every field: the generated struct is part of
+    /// the crate's public API, and `#![warn(missing_docs)]` applies to generated code exactly
+    /// like hand-written code.
+    doc: &'static str,
+}
+
+struct GeneratedMessage {
+    mesg_num_variant: &'static str,
+    struct_name: &'static str,
+    fields: &'static [GeneratedField],
+}
+
+/// One entry per FIT message this crate knows enough about to generate a typed struct for.
+/// Reusing a message here means giving every field it should expose its own `GeneratedField`;
+/// there's no separate profile description to read this from. Growing this list (and, for scaled
+/// fields, `crate::profile`'s field tables) is how support for more messages and fields is added.
+const MESSAGES: &[GeneratedMessage] = &[
+    GeneratedMessage {
+        mesg_num_variant: "Record",
+        struct_name: "Record",
+        fields: &[
+            GeneratedField {
+                rust_name: "timestamp",
+                field_number: 253,
+                rust_type: "Option<chrono::DateTime<chrono::FixedOffset>>",
+                value_variant: "Timestamp",
+                doc: "When this sample was recorded.",
+            },
+            GeneratedField {
+                rust_name: "heart_rate",
+                field_number: 3,
+                rust_type: "Option<u8>",
+                value_variant: "UInt8",
+                doc: "Heart rate in beats per minute.",
+            },
+            GeneratedField {
+                rust_name: "cadence",
+                field_number: 4,
+                rust_type: "Option<u8>",
+                value_variant: "UInt8",
+                doc: "Cadence in revolutions per minute.",
+            },
+            GeneratedField {
+                rust_name: "power",
+                field_number: 7,
+                rust_type: "Option<u16>",
+                value_variant: "UInt16",
+                doc: "Power output in watts.",
+            },
+            GeneratedField {
+                rust_name: "altitude",
+                field_number: 2,
+                rust_type: "Option<f64>",
+                value_variant: "Float64",
+                doc: "Altitude in meters. Scaled by `crate::profile` when `DeserializeOptions::apply_profile_scaling` is on; otherwise converted from the field's raw integer value.",
+            },
+            GeneratedField {
+                rust_name: "speed",
+                field_number: 6,
+                rust_type: "Option<f64>",
+                value_variant: "Float64",
+                doc: "Speed in meters per second. Scaled by `crate::profile` when `DeserializeOptions::apply_profile_scaling` is on; otherwise converted from the field's raw integer value.",
+            },
+            GeneratedField {
+                rust_name: "distance",
+                field_number: 5,
+                rust_type: "Option<f64>",
+                value_variant: "Float64",
+                doc: "Cumulative distance in meters. Scaled by `crate::profile` when `DeserializeOptions::apply_profile_scaling` is on; otherwise converted from the field's raw integer value.",
+            },
+        ],
+    },
+    GeneratedMessage {
+        mesg_num_variant: "FileId",
+        struct_name: "FileId",
+        fields: &[
+            GeneratedField {
+                rust_name: "manufacturer",
+                field_number: 1,
+                rust_type: "Option<u16>",
+                value_variant: "UInt16",
+                doc: "Manufacturer that produced the device, as a FIT manufacturer id.",
+            },
+            GeneratedField {
+                rust_name: "product",
+                field_number: 2,
+                rust_type: "Option<u16>",
+                value_variant: "UInt16",
+                doc: "Manufacturer-assigned product id.",
+            },
+            GeneratedField {
+                rust_name: "serial_number",
+                field_number: 3,
+                rust_type: "Option<u32>",
+                value_variant: "UInt32z",
+                doc: "Device serial number.",
+            },
+            GeneratedField {
+                rust_name: "time_created",
+                field_number: 4,
+                rust_type: "Option<chrono::DateTime<chrono::FixedOffset>>",
+                value_variant: "Timestamp",
+                doc: "When this file was created.",
+            },
+        ],
+    },
+];
+
+/// Render the full `messages.rs` source: one struct and one `TryFrom<FitDataRecord>` impl per
+/// entry in [`MESSAGES`].
+fn generate() -> String {
+    let mut out = String::new();
+    out.push_str("// @generated from the MESSAGES table in build/codegen.rs. Do not edit by hand.\n\n");
+    for message in MESSAGES {
+        out.push_str(&format!(
+            "/// Typed view of a `{}` message. Only the fields listed for it in `build/codegen.rs` are exposed.\n",
+            message.mesg_num_variant
+        ));
+        out.push_str(&format!(
+            "#[derive(Clone, Debug, Default, PartialEq)]\npub struct {} {{\n",
+            message.struct_name
+        ));
+        for field in message.fields {
+            out.push_str(&format!("    /// {}\n", field.doc));
+            out.push_str(&format!("    pub {}: {},\n", field.rust_name, field.rust_type));
+        }
+        out.push_str("}\n\n");
+
+        out.push_str(&format!(
+            "impl std::convert::TryFrom<crate::FitDataRecord> for {} {{\n",
+            message.struct_name
+        ));
+        out.push_str("    type Error = crate::Error;\n\n");
+        out.push_str("    fn try_from(record: crate::FitDataRecord) -> crate::Result<Self> {\n");
+        out.push_str(&format!(
+            "        if record.kind() != crate::profile::MesgNum::{} {{\n",
+            message.mesg_num_variant
+        ));
+        out.push_str(&format!(
+            "            return Err(crate::ErrorKind::Custom(format!(\"expected a {} message, got {{}}\", record.kind())).into());\n",
+            message.mesg_num_variant
+        ));
+        out.push_str("        }\n");
+        out.push_str(&format!("        let mut typed = {}::default();\n", message.struct_name));
+        out.push_str("        for field in record.fields() {\n");
+        out.push_str("            match field.number() {\n");
+        for field in message.fields {
+            if field.rust_type == "Option<f64>" {
+                // Scaled fields decode as `Value::Float64` only when the caller opted into
+                // `DeserializeOptions::apply_profile_scaling`; otherwise the field is still
+                // present, just as its raw unscaled `Value` variant. Go through `TryInto<f64>`
+                // so typed access works either way instead of silently staying `None`.
+                out.push_str(&format!(
+                    "                {} => if let Ok(v) = std::convert::TryInto::<f64>::try_into(field.value().clone()) {{ typed.{} = Some(v); }},\n",
+                    field.field_number, field.rust_name
+                ));
+            } else if field.value_variant == "Timestamp" {
+                // The parser decodes only field 253 as `Value::Timestamp`; other date_time fields
+                // (e.g. `file_id.time_created`) arrive as raw FIT-epoch seconds in a `UInt32`.
+                out.push_str(&format!(
+                    "                {} => match field.value().clone() {{ crate::Value::Timestamp(v) => typed.{} = Some(v), crate::Value::UInt32(secs) if secs != 0xFFFF_FFFF => typed.{} = Some(chrono::TimeZone::timestamp(&chrono::FixedOffset::east(0), secs as i64 + 631_065_600, 0)), _ => {{}} }},\n",
+                    field.field_number, field.rust_name, field.rust_name
+                ));
+            } else {
+                out.push_str(&format!(
+                    "                {} => if let crate::Value::{}(v) = field.value().clone() {{ typed.{} = Some(v); }},\n",
+                    field.field_number, field.value_variant, field.rust_name
+                ));
+            }
+        }
+        out.push_str("                _ => {}\n");
+        out.push_str("            }\n");
+        out.push_str("        }\n");
+        out.push_str("        Ok(typed)\n");
+        out.push_str("    }\n");
+        out.push_str("}\n\n");
+    }
+    out
+}
@@ -0,0 +1,15 @@
+//! Stand-alone entry point for the generator `build.rs` also runs automatically. Useful for
+//! inspecting the generated code (`cargo run --bin fitgen`) or writing it to a file
+//! (`cargo run --bin fitgen -- path/to/out.rs`) without forcing a full crate rebuild.
+use std::env;
+use std::fs;
+
+include!("../../build/codegen.rs");
+
+fn main() {
+    let code = generate();
+    match env::args().nth(1) {
+        Some(path) => fs::write(&path, code).expect("failed to write output file"),
+        None => print!("{}", code),
+    }
+}
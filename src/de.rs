@@ -0,0 +1,785 @@
+//! Parses a FIT binary data stream into [`FitDataRecord`]s.
+//!
+//! The message framing mirrors what [`crate::write`] emits: a file header, a sequence of
+//! definition and data messages, and a trailing CRC-16 over the header and body bytes.
+use std::io::Read;
+
+use chrono::TimeZone;
+
+use crate::write::{crc16, crc_update};
+use crate::{profile::MesgNum, DeserializeOptions, ErrorKind, FitDataField, FitDataRecord, Result, Value};
+
+const FIT_EPOCH_OFFSET: i64 = 631_065_600;
+
+/// The parsed contents of a FIT file header, returned by [`Deserializer::parse`] alongside the
+/// records so callers can detect truncated or corrupt input instead of silently getting a
+/// partial result.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FileHeader {
+    /// FIT protocol version the file was written with.
+    pub protocol_version: u8,
+    /// FIT profile version the file was written against.
+    pub profile_version: u16,
+    /// Size in bytes of the data section, not counting the header or trailing CRC.
+    pub data_size: u32,
+}
+
+/// Definition of a single field within a FIT definition message: its field number, encoded size,
+/// and base type byte, used to decode the data messages that follow.
+#[derive(Clone, Debug)]
+struct FieldDefinition {
+    number: u8,
+    size: u8,
+    base_type: u8,
+}
+
+/// Definition message for the (single) local message slot this crate's writer uses.
+#[derive(Clone, Debug)]
+struct MessageDefinition {
+    global_mesg_num: u16,
+    fields: Vec<FieldDefinition>,
+}
+
+/// Parses FIT data streams, with CRC verification disabled and the default [`DeserializeOptions`]
+/// in effect unless configured otherwise, matching the historical behavior of `from_bytes`/
+/// `from_reader`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Deserializer {
+    verify_crc: bool,
+    options: DeserializeOptions,
+}
+
+impl Deserializer {
+    /// Create a new `Deserializer` with CRC verification disabled and default options.
+    pub fn new() -> Self {
+        Deserializer {
+            verify_crc: false,
+            options: DeserializeOptions::default(),
+        }
+    }
+
+    /// Verify the header and trailing body CRC-16 while parsing, returning
+    /// [`ErrorKind::CrcMismatch`] if either doesn't match the bytes actually read.
+    pub fn verify_crc(mut self, verify: bool) -> Self {
+        self.verify_crc = verify;
+        self
+    }
+
+    /// Replace the [`DeserializeOptions`] used while parsing (timestamp timezone, allocation
+    /// limits, and the invalid-sentinel field policy).
+    pub fn options(mut self, options: DeserializeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Parse `reader` one data message at a time instead of materializing a `Vec<FitDataRecord>`
+    /// up front. Only the current segment's remaining byte count and running CRC, its most
+    /// recent definition message, and the bytes of the field being decoded are held in memory at
+    /// once, so this composes well with filtering/aggregating large monitoring or activity files.
+    /// Developer field descriptions are not tracked. Chained FIT segments are followed
+    /// transparently, the same as [`from_reader`].
+    pub fn records<R: Read>(&self, reader: R) -> RecordIter<R> {
+        RecordIter {
+            reader,
+            deserializer: *self,
+            segment: None,
+            finished: false,
+        }
+    }
+
+    /// Whether CRC verification is enabled, either through [`Deserializer::verify_crc`] directly
+    /// or through a [`DeserializeOptions`] with [`DeserializeOptions::verify_crc`] set.
+    fn verify_crc_enabled(&self) -> bool {
+        self.verify_crc || self.options.verify_crc
+    }
+
+    /// Parse a single FIT data stream, returning its header alongside the decoded records.
+    ///
+    /// FIT files can be chained, so this only consumes one header/body/CRC segment; callers that
+    /// need every segment in a chained file should keep calling `parse` until the reader is
+    /// exhausted, as [`from_reader`] does.
+    pub fn parse<R: Read>(&self, reader: &mut R) -> Result<(FileHeader, Vec<FitDataRecord>)> {
+        match self.try_parse_segment(reader)? {
+            Some(result) => Ok(result),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "no more FIT segments in this reader",
+            )
+            .into()),
+        }
+    }
+
+    /// Parse the next header/body/CRC segment, or return `Ok(None)` if the reader was cleanly
+    /// exhausted right at a segment boundary (the normal end of a chained file). Any other read
+    /// failure - including one partway through a header, body, or trailing CRC - is a genuine
+    /// truncation/corruption and is returned as `Err`, never folded into the clean-EOF case.
+    fn try_parse_segment<R: Read>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Option<(FileHeader, Vec<FitDataRecord>)>> {
+        let mut header_bytes = [0u8; 12];
+        let first_byte_count = reader.read(&mut header_bytes[..1])?;
+        if first_byte_count == 0 {
+            return Ok(None);
+        }
+        reader.read_exact(&mut header_bytes[1..])?;
+        let header_size = header_bytes[0];
+        let protocol_version = header_bytes[1];
+        let profile_version = u16::from_le_bytes([header_bytes[2], header_bytes[3]]);
+        let data_size = u32::from_le_bytes([
+            header_bytes[4],
+            header_bytes[5],
+            header_bytes[6],
+            header_bytes[7],
+        ]);
+        if &header_bytes[8..12] != b".FIT" {
+            return Err(ErrorKind::Custom("missing '.FIT' file signature".to_string()).into());
+        }
+
+        let mut extra_header_bytes = Vec::new();
+        if header_size > 12 {
+            let mut rest = vec![0u8; header_size as usize - 12];
+            reader.read_exact(&mut rest)?;
+            extra_header_bytes = rest;
+        }
+        if self.verify_crc_enabled() && extra_header_bytes.len() >= 2 {
+            let expected = u16::from_le_bytes([extra_header_bytes[0], extra_header_bytes[1]]);
+            let computed = crc16(&header_bytes);
+            if expected != computed {
+                return Err(ErrorKind::CrcMismatch { expected, computed }.into());
+            }
+        }
+
+        if data_size > self.options.max_data_size {
+            return Err(ErrorKind::Custom(format!(
+                "declared data_size {} exceeds the configured limit of {} bytes",
+                data_size, self.options.max_data_size
+            ))
+            .into());
+        }
+        let mut body = vec![0u8; data_size as usize];
+        reader.read_exact(&mut body)?;
+
+        if self.verify_crc_enabled() {
+            let mut trailing_crc = [0u8; 2];
+            reader.read_exact(&mut trailing_crc)?;
+            let expected = u16::from_le_bytes(trailing_crc);
+            let full: Vec<u8> = header_bytes
+                .iter()
+                .chain(extra_header_bytes.iter())
+                .chain(body.iter())
+                .copied()
+                .collect();
+            let computed = crc16(&full);
+            if expected != computed {
+                return Err(ErrorKind::CrcMismatch { expected, computed }.into());
+            }
+        } else {
+            // Still consume the trailing CRC bytes so chained files parse correctly.
+            let mut trailing_crc = [0u8; 2];
+            reader.read_exact(&mut trailing_crc)?;
+        }
+
+        let header = FileHeader {
+            protocol_version,
+            profile_version,
+            data_size,
+        };
+        let records = parse_body(&body, &self.options)?;
+        Ok(Some((header, records)))
+    }
+}
+
+/// Per-segment state for [`RecordIter`]: how many body bytes are left to read, the running CRC
+/// over everything read so far (header, extra header bytes, and body), and the most recently
+/// seen definition message.
+struct SegmentState {
+    remaining_body_bytes: u32,
+    running_crc: u16,
+    definition: Option<MessageDefinition>,
+}
+
+/// A streaming record-at-a-time parser returned by [`Deserializer::records`]. Implements
+/// [`Iterator`], yielding one `Result<FitDataRecord>` per data message without ever buffering a
+/// whole segment's body or the whole file in memory.
+pub struct RecordIter<R> {
+    reader: R,
+    deserializer: Deserializer,
+    segment: Option<SegmentState>,
+    finished: bool,
+}
+
+impl<R: Read> RecordIter<R> {
+    /// Read `n` bytes from the reader, folding each into the segment's running CRC and charging
+    /// it against the segment's remaining body-byte budget.
+    fn read_body_bytes(&mut self, n: usize) -> Result<Vec<u8>> {
+        let segment = self.segment.as_mut().expect("read_body_bytes called outside a segment");
+        if n as u32 > segment.remaining_body_bytes {
+            return Err(ErrorKind::Custom(
+                "definition/data message runs past the segment's declared data_size".to_string(),
+            )
+            .into());
+        }
+        let mut buf = vec![0u8; n];
+        self.reader.read_exact(&mut buf)?;
+        for &byte in &buf {
+            segment.running_crc = crc_update(segment.running_crc, byte);
+        }
+        segment.remaining_body_bytes -= n as u32;
+        Ok(buf)
+    }
+
+    /// Read the next segment's header, seeding the running CRC from it. Returns `Ok(false)` if
+    /// the reader was cleanly exhausted between segments (the normal end of a chained file).
+    fn start_segment(&mut self) -> Result<bool> {
+        let mut first_byte = [0u8; 1];
+        if self.reader.read(&mut first_byte)? == 0 {
+            return Ok(false);
+        }
+        let mut header_bytes = [0u8; 12];
+        header_bytes[0] = first_byte[0];
+        self.reader.read_exact(&mut header_bytes[1..])?;
+
+        let header_size = header_bytes[0];
+        let data_size = u32::from_le_bytes([
+            header_bytes[4],
+            header_bytes[5],
+            header_bytes[6],
+            header_bytes[7],
+        ]);
+        if &header_bytes[8..12] != b".FIT" {
+            return Err(ErrorKind::Custom("missing '.FIT' file signature".to_string()).into());
+        }
+        if data_size > self.deserializer.options.max_data_size {
+            return Err(ErrorKind::Custom(format!(
+                "declared data_size {} exceeds the configured limit of {} bytes",
+                data_size, self.deserializer.options.max_data_size
+            ))
+            .into());
+        }
+
+        let mut running_crc = header_bytes.iter().fold(0u16, |crc, &b| crc_update(crc, b));
+        if header_size > 12 {
+            let mut extra = vec![0u8; header_size as usize - 12];
+            self.reader.read_exact(&mut extra)?;
+            if self.deserializer.verify_crc_enabled() && extra.len() >= 2 {
+                let expected = u16::from_le_bytes([extra[0], extra[1]]);
+                let computed = crc16(&header_bytes);
+                if expected != computed {
+                    return Err(ErrorKind::CrcMismatch { expected, computed }.into());
+                }
+            }
+            running_crc = extra.iter().fold(running_crc, |crc, &b| crc_update(crc, b));
+        }
+
+        self.segment = Some(SegmentState {
+            remaining_body_bytes: data_size,
+            running_crc,
+            definition: None,
+        });
+        Ok(true)
+    }
+
+    /// Parse the next message out of the current segment's body. `Ok(None)` means the segment's
+    /// body is exhausted (the trailing CRC has been consumed and checked); the caller should then
+    /// try to start the next chained segment.
+    fn next_in_segment(&mut self) -> Result<Option<FitDataRecord>> {
+        loop {
+            let remaining = self.segment.as_ref().unwrap().remaining_body_bytes;
+            if remaining == 0 {
+                let mut trailing_crc = [0u8; 2];
+                self.reader.read_exact(&mut trailing_crc)?;
+                if self.deserializer.verify_crc_enabled() {
+                    let expected = u16::from_le_bytes(trailing_crc);
+                    let computed = self.segment.as_ref().unwrap().running_crc;
+                    if expected != computed {
+                        return Err(ErrorKind::CrcMismatch { expected, computed }.into());
+                    }
+                }
+                return Ok(None);
+            }
+
+            let record_header = self.read_body_bytes(1)?[0];
+            if record_header & 0x40 != 0 {
+                let reserved_and_arch = self.read_body_bytes(2)?;
+                let _reserved = reserved_and_arch[0];
+                let _architecture = reserved_and_arch[1];
+                let mesg_num_bytes = self.read_body_bytes(2)?;
+                let global_mesg_num = u16::from_le_bytes([mesg_num_bytes[0], mesg_num_bytes[1]]);
+                let num_fields = self.read_body_bytes(1)?[0];
+                let mut fields = Vec::with_capacity(num_fields as usize);
+                for _ in 0..num_fields {
+                    let def_bytes = self.read_body_bytes(3)?;
+                    fields.push(FieldDefinition {
+                        number: def_bytes[0],
+                        size: def_bytes[1],
+                        base_type: def_bytes[2],
+                    });
+                }
+                self.segment.as_mut().unwrap().definition = Some(MessageDefinition {
+                    global_mesg_num,
+                    fields,
+                });
+                continue;
+            }
+
+            let definition = self
+                .segment
+                .as_ref()
+                .unwrap()
+                .definition
+                .clone()
+                .ok_or_else(|| -> crate::Error {
+                    ErrorKind::Custom("data message with no preceding definition message".to_string())
+                        .into()
+                })?;
+            let mesg = MesgNum::from(definition.global_mesg_num);
+            let mut record = FitDataRecord::new(mesg);
+            for field in &definition.fields {
+                let field_bytes = self.read_body_bytes(field.size as usize)?;
+                let (value, _) = read_value(&field_bytes, field, &self.deserializer.options)?;
+                if self.deserializer.options.invalid_field_policy == crate::InvalidFieldPolicy::Drop
+                    && !value.is_valid()
+                {
+                    continue;
+                }
+                if self.deserializer.options.apply_profile_scaling {
+                    for (number, name, value, units) in
+                        crate::profile::decode(mesg, field.number, value)
+                    {
+                        record.push(FitDataField::new(name, number, value, units));
+                    }
+                } else {
+                    record.push(FitDataField::new(
+                        format!("field_{}", field.number),
+                        field.number,
+                        value,
+                        String::new(),
+                    ));
+                }
+            }
+            return Ok(Some(record));
+        }
+    }
+}
+
+impl<R: Read> Iterator for RecordIter<R> {
+    type Item = Result<FitDataRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.finished {
+                return None;
+            }
+            if self.segment.is_none() {
+                match self.start_segment() {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        self.finished = true;
+                        return None;
+                    }
+                    Err(err) => {
+                        self.finished = true;
+                        return Some(Err(err));
+                    }
+                }
+            }
+            match self.next_in_segment() {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => {
+                    self.segment = None;
+                    continue;
+                }
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+fn parse_body(mut body: &[u8], options: &DeserializeOptions) -> Result<Vec<FitDataRecord>> {
+    let mut records = Vec::new();
+    let mut definition: Option<MessageDefinition> = None;
+
+    while !body.is_empty() {
+        let record_header = body[0];
+        body = &body[1..];
+        let is_definition = record_header & 0x40 != 0;
+
+        if is_definition {
+            let (reserved_and_arch, rest) = body.split_at(2);
+            let _reserved = reserved_and_arch[0];
+            let _architecture = reserved_and_arch[1];
+            body = rest;
+            let global_mesg_num = u16::from_le_bytes([body[0], body[1]]);
+            let num_fields = body[2];
+            body = &body[3..];
+            let mut fields = Vec::with_capacity(num_fields as usize);
+            for _ in 0..num_fields {
+                fields.push(FieldDefinition {
+                    number: body[0],
+                    size: body[1],
+                    base_type: body[2],
+                });
+                body = &body[3..];
+            }
+            definition = Some(MessageDefinition {
+                global_mesg_num,
+                fields,
+            });
+        } else {
+            let definition = definition
+                .as_ref()
+                .ok_or_else(|| -> crate::Error {
+                    ErrorKind::Custom("data message with no preceding definition message".to_string())
+                        .into()
+                })?;
+            let mesg = MesgNum::from(definition.global_mesg_num);
+            let mut record = FitDataRecord::new(mesg);
+            for field in &definition.fields {
+                let (value, rest) = read_value(body, field, options)?;
+                body = rest;
+                if options.invalid_field_policy == crate::InvalidFieldPolicy::Drop && !value.is_valid()
+                {
+                    continue;
+                }
+
+                if options.apply_profile_scaling {
+                    for (number, name, value, units) in
+                        crate::profile::decode(mesg, field.number, value)
+                    {
+                        record.push(FitDataField::new(name, number, value, units));
+                    }
+                } else {
+                    record.push(FitDataField::new(
+                        format!("field_{}", field.number),
+                        field.number,
+                        value,
+                        String::new(),
+                    ));
+                }
+            }
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Convert `bytes` into a fixed-size array, returning an `ErrorKind::Custom` error (instead of
+/// panicking) when a hostile or corrupt definition message declares a field `size` that doesn't
+/// match the byte width its `base_type` actually needs.
+fn fixed_bytes<const N: usize>(bytes: &[u8], field: &FieldDefinition) -> Result<[u8; N]> {
+    bytes.try_into().map_err(|_| -> crate::Error {
+        ErrorKind::Custom(format!(
+            "field {} declares size {} but base type {:#04x} needs {} bytes",
+            field.number, field.size, field.base_type, N
+        ))
+        .into()
+    })
+}
+
+/// Decode `bytes` as one or more `N`-byte elements of a fixed-width base type, mirroring
+/// [`crate::write::base_type_of`]'s encoding of `Value::Array`: a field whose declared `size` is
+/// a single element decodes to a scalar `Value`, one whose `size` is a multiple of `N` greater
+/// than one decodes to `Value::Array`, and any other size is a malformed definition message.
+fn decode_elements<const N: usize>(
+    bytes: &[u8],
+    field: &FieldDefinition,
+    to_value: impl Fn([u8; N]) -> Value,
+) -> Result<Value> {
+    if bytes.is_empty() || bytes.len() % N != 0 {
+        return Err(ErrorKind::Custom(format!(
+            "field {} declares size {}, which isn't a multiple of the {} bytes its base type {:#04x} needs",
+            field.number, field.size, N, field.base_type
+        ))
+        .into());
+    }
+    let mut values: Vec<Value> = bytes
+        .chunks_exact(N)
+        .map(|chunk| to_value(chunk.try_into().expect("chunks_exact guarantees a length-N chunk")))
+        .collect();
+    if values.len() == 1 {
+        Ok(values.pop().expect("just checked values.len() == 1"))
+    } else {
+        Ok(Value::Array(values))
+    }
+}
+
+fn read_value<'a>(
+    body: &'a [u8],
+    field: &FieldDefinition,
+    options: &DeserializeOptions,
+) -> Result<(Value, &'a [u8])> {
+    let size = field.size as usize;
+    if body.len() < size {
+        return Err(ErrorKind::Custom(format!(
+            "truncated data message: need {} more bytes for field {}",
+            size - body.len(),
+            field.number
+        ))
+        .into());
+    }
+    let (bytes, rest) = body.split_at(size);
+    let value = match field.base_type {
+        0x00 => decode_elements::<1>(bytes, field, |b| Value::Enum(b[0]))?,
+        0x01 => decode_elements::<1>(bytes, field, |b| Value::SInt8(b[0] as i8))?,
+        0x02 => decode_elements::<1>(bytes, field, |b| Value::UInt8(b[0]))?,
+        0x0A => decode_elements::<1>(bytes, field, |b| Value::UInt8z(b[0]))?,
+        0x0D => decode_elements::<1>(bytes, field, |b| Value::Byte(b[0]))?,
+        0x83 => decode_elements::<2>(bytes, field, |b| Value::SInt16(i16::from_le_bytes(b)))?,
+        0x84 => decode_elements::<2>(bytes, field, |b| Value::UInt16(u16::from_le_bytes(b)))?,
+        0x8B => decode_elements::<2>(bytes, field, |b| Value::UInt16z(u16::from_le_bytes(b)))?,
+        0x85 => decode_elements::<4>(bytes, field, |b| Value::SInt32(i32::from_le_bytes(b)))?,
+        0x86 if field.number == 253 => {
+            let fit_epoch_secs = u32::from_le_bytes(fixed_bytes(bytes, field)?);
+            let unix_timestamp = fit_epoch_secs as i64 + FIT_EPOCH_OFFSET;
+            let offset = options.timestamp_timezone.offset(unix_timestamp);
+            Value::Timestamp(offset.timestamp(unix_timestamp, 0))
+        }
+        0x86 => decode_elements::<4>(bytes, field, |b| Value::UInt32(u32::from_le_bytes(b)))?,
+        0x8C => decode_elements::<4>(bytes, field, |b| Value::UInt32z(u32::from_le_bytes(b)))?,
+        0x88 => decode_elements::<4>(bytes, field, |b| Value::Float32(f32::from_le_bytes(b)))?,
+        0x89 => decode_elements::<8>(bytes, field, |b| Value::Float64(f64::from_le_bytes(b)))?,
+        0x8E => decode_elements::<8>(bytes, field, |b| Value::SInt64(i64::from_le_bytes(b)))?,
+        0x8F => decode_elements::<8>(bytes, field, |b| Value::UInt64(u64::from_le_bytes(b)))?,
+        0x90 => decode_elements::<8>(bytes, field, |b| Value::UInt64z(u64::from_le_bytes(b)))?,
+        0x07 => {
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            Value::String(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        }
+        other => {
+            return Err(ErrorKind::Custom(format!("unrecognized FIT base type {:#04x}", other)).into())
+        }
+    };
+    Ok((value, rest))
+}
+
+/// Parse every FIT record from `reader`, following chained file headers until the reader is
+/// exhausted, using the crate's default [`DeserializeOptions`] (UTC timestamps).
+pub fn from_reader<R: Read>(reader: &mut R) -> Result<Vec<FitDataRecord>> {
+    from_reader_with_options(reader, DeserializeOptions::default())
+}
+
+/// Same as [`from_reader`], but with a caller-supplied [`DeserializeOptions`]. Setting
+/// [`DeserializeOptions::verify_crc`] verifies every chained segment's CRC, not just the first.
+pub fn from_reader_with_options<R: Read>(
+    reader: &mut R,
+    options: DeserializeOptions,
+) -> Result<Vec<FitDataRecord>> {
+    let deserializer = Deserializer::new().options(options);
+    let mut records = Vec::new();
+    while let Some((_header, mut batch)) = deserializer.try_parse_segment(reader)? {
+        records.append(&mut batch);
+    }
+    Ok(records)
+}
+
+/// Parse every FIT record out of an in-memory byte buffer.
+pub fn from_bytes(data: &[u8]) -> Result<Vec<FitDataRecord>> {
+    from_reader(&mut std::io::Cursor::new(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{profile::MesgNum, FitDataRecord};
+
+    fn sample_record() -> FitDataRecord {
+        let mut record = FitDataRecord::new(MesgNum::Record);
+        record.push(FitDataField::new("heart_rate".to_string(), 3, Value::UInt8(128), String::new()));
+        record
+    }
+
+    #[test]
+    fn round_trips_through_the_writer() {
+        let records = vec![sample_record(), sample_record()];
+        let mut buf = Vec::new();
+        crate::write::to_writer(&records, &mut buf).unwrap();
+
+        let parsed = from_bytes(&buf).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].kind(), MesgNum::Record);
+        assert_eq!(parsed[0].fields()[0].number(), 3);
+    }
+
+    #[test]
+    fn round_trips_an_array_field_through_the_writer() {
+        let mut record = FitDataRecord::new(MesgNum::Record);
+        record.push(FitDataField::new(
+            "field_250".to_string(),
+            250,
+            Value::Array(vec![Value::UInt16(1), Value::UInt16(2), Value::UInt16(3)]),
+            String::new(),
+        ));
+        let mut buf = Vec::new();
+        crate::write::to_writer(&[record], &mut buf).unwrap();
+
+        let parsed = from_bytes(&buf).unwrap();
+        assert_eq!(
+            parsed[0].fields()[0].value(),
+            &Value::Array(vec![Value::UInt16(1), Value::UInt16(2), Value::UInt16(3)])
+        );
+    }
+
+    #[test]
+    fn a_multi_byte_size_on_a_single_byte_base_type_decodes_as_an_array() {
+        // A single-byte base type (here 0x02, UInt8) with size 3 has the same shape as
+        // record.compressed_speed_distance, a packed 3-byte field: it decodes as an array, not a scalar.
+        let field = FieldDefinition {
+            number: 8,
+            size: 3,
+            base_type: 0x02,
+        };
+        let (value, rest) = read_value(&[1, 2, 3], &field, &DeserializeOptions::default()).unwrap();
+        assert_eq!(value, Value::Array(vec![Value::UInt8(1), Value::UInt8(2), Value::UInt8(3)]));
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn detects_a_corrupted_trailing_crc() {
+        let records = vec![sample_record()];
+        let mut buf = Vec::new();
+        crate::write::to_writer(&records, &mut buf).unwrap();
+        let last = buf.len() - 1;
+        buf[last] ^= 0xFF;
+
+        let err = Deserializer::new().verify_crc(true).parse(&mut std::io::Cursor::new(&buf[..]));
+        assert!(matches!(*err.unwrap_err(), ErrorKind::CrcMismatch { .. }));
+    }
+
+    #[test]
+    fn from_reader_with_options_verifies_crc_of_every_chained_segment() {
+        let mut buf = Vec::new();
+        crate::write::to_writer(&[sample_record()], &mut buf).unwrap();
+        crate::write::to_writer(&[sample_record()], &mut buf).unwrap();
+        // Corrupt a byte inside the second segment's body, past the first segment entirely.
+        let corrupt_at = buf.len() - 3;
+        buf[corrupt_at] ^= 0xFF;
+
+        let options = DeserializeOptions::new().verify_crc(true);
+        let err = from_reader_with_options(&mut std::io::Cursor::new(&buf[..]), options).unwrap_err();
+        assert!(matches!(*err, ErrorKind::CrcMismatch { .. }));
+    }
+
+    #[test]
+    fn from_reader_with_options_reports_a_file_truncated_mid_segment() {
+        let mut buf = Vec::new();
+        crate::write::to_writer(&[sample_record()], &mut buf).unwrap();
+        crate::write::to_writer(&[sample_record()], &mut buf).unwrap();
+        // Cut the stream a few bytes into the second segment's header instead of at a clean
+        // segment boundary - this must surface as an error, not a silently-truncated result.
+        let first_segment_len = {
+            let mut one = Vec::new();
+            crate::write::to_writer(&[sample_record()], &mut one).unwrap();
+            one.len()
+        };
+        buf.truncate(first_segment_len + 4);
+
+        let result = from_reader_with_options(&mut std::io::Cursor::new(&buf[..]), DeserializeOptions::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_a_data_size_above_the_configured_limit() {
+        let records = vec![sample_record()];
+        let mut buf = Vec::new();
+        crate::write::to_writer(&records, &mut buf).unwrap();
+
+        let options = DeserializeOptions::new().max_data_size(1);
+        let result =
+            from_reader_with_options(&mut std::io::Cursor::new(&buf[..]), options);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn drops_invalid_fields_when_configured_to() {
+        let mut record = FitDataRecord::new(MesgNum::Record);
+        record.push(FitDataField::new(
+            "heart_rate".to_string(),
+            3,
+            Value::UInt8(0xFF), // the FIT invalid sentinel
+            String::new(),
+        ));
+        let mut buf = Vec::new();
+        crate::write::to_writer(&[record], &mut buf).unwrap();
+
+        let options = DeserializeOptions::new().invalid_field_policy(crate::InvalidFieldPolicy::Drop);
+        let parsed = from_reader_with_options(&mut std::io::Cursor::new(&buf[..]), options).unwrap();
+        assert!(parsed[0].fields().is_empty());
+    }
+
+    #[test]
+    fn expands_compressed_speed_distance_when_scaling_is_enabled() {
+        let mut record = FitDataRecord::new(MesgNum::Record);
+        let packed = 100u32 | (16u32 << 12); // 1 m/s, 1 m
+        record.push(FitDataField::new("field_8".to_string(), 8, Value::UInt32(packed), String::new()));
+        let mut buf = Vec::new();
+        crate::write::to_writer(&[record], &mut buf).unwrap();
+
+        let options = DeserializeOptions::new().apply_profile_scaling(true);
+        let parsed = from_reader_with_options(&mut std::io::Cursor::new(&buf[..]), options).unwrap();
+        let fields = parsed[0].fields();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name(), "speed");
+        assert_eq!(fields[1].name(), "distance");
+    }
+
+    #[test]
+    fn streaming_iterator_matches_the_vec_based_parse() {
+        let records = vec![sample_record(), sample_record(), sample_record()];
+        let mut buf = Vec::new();
+        crate::write::to_writer(&records, &mut buf).unwrap();
+
+        let streamed: Result<Vec<FitDataRecord>> =
+            Deserializer::new().records(std::io::Cursor::new(&buf[..])).collect();
+        let streamed = streamed.unwrap();
+        assert_eq!(streamed.len(), 3);
+        assert_eq!(streamed[0].kind(), MesgNum::Record);
+        assert_eq!(streamed[2].fields()[0].number(), 3);
+    }
+
+    #[test]
+    fn streaming_iterator_follows_chained_segments() {
+        let mut buf = Vec::new();
+        crate::write::to_writer(&[sample_record()], &mut buf).unwrap();
+        crate::write::to_writer(&[sample_record(), sample_record()], &mut buf).unwrap();
+
+        let count = Deserializer::new()
+            .records(std::io::Cursor::new(&buf[..]))
+            .filter(|r| r.is_ok())
+            .count();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn rejects_a_field_whose_declared_size_does_not_match_its_base_type() {
+        // base_type 0x85 (SInt32) needs 4 bytes to decode, but the definition only declares 3 -
+        // this must return an error instead of panicking.
+        let field = FieldDefinition {
+            number: 0,
+            size: 3,
+            base_type: 0x85,
+        };
+        let body = [0u8; 3];
+        let err = read_value(&body, &field, &DeserializeOptions::default()).unwrap_err();
+        assert!(matches!(*err, ErrorKind::Custom(_)));
+    }
+
+    #[test]
+    fn streaming_iterator_reports_a_crc_mismatch() {
+        let mut buf = Vec::new();
+        crate::write::to_writer(&[sample_record()], &mut buf).unwrap();
+        let last = buf.len() - 1;
+        buf[last] ^= 0xFF;
+
+        let mut iter = Deserializer::new()
+            .verify_crc(true)
+            .records(std::io::Cursor::new(&buf[..]));
+        let first = iter.next().unwrap();
+        assert!(matches!(first, Ok(_)));
+        let second = iter.next();
+        assert!(matches!(second, Some(Err(_))));
+    }
+}
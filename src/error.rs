@@ -25,7 +25,16 @@ pub enum ErrorKind {
     /// Errors generated by trying to parse invalid data with a nom combinator
     ParseError(usize, nom::error::ErrorKind),
     /// Errors tied to insufficent data in the buffer, similar to an IO error but coming from nom
-    UnexpectedEof(nom::Needed)
+    UnexpectedEof(nom::Needed),
+    /// The CRC-16 recorded in the file (header or trailing body CRC) didn't match the CRC
+    /// computed while parsing, meaning the file is truncated or corrupt. Only produced when CRC
+    /// verification is enabled on the [`crate::Deserializer`].
+    CrcMismatch {
+        /// The CRC value stored in the file.
+        expected: u16,
+        /// The CRC value computed from the bytes actually read.
+        computed: u16,
+    },
 }
 
 impl StdError for ErrorKind {
@@ -36,6 +45,7 @@ impl StdError for ErrorKind {
             ErrorKind::DeserializeAnyNotSupported => None,
             ErrorKind::ParseError(..) => None,
             ErrorKind::UnexpectedEof(..) => None,
+            ErrorKind::CrcMismatch { .. } => None,
         }
     }
 }
@@ -65,6 +75,11 @@ impl fmt::Display for ErrorKind {
             ErrorKind::ParseError(rem, ref err) => write!(fmt, "parser error: '{}' bytes remaining: {}", err.description(), rem),
             ErrorKind::UnexpectedEof(nom::Needed::Size(n)) => write!(fmt, "parser error: requires {} more bytes", n),
             ErrorKind::UnexpectedEof(nom::Needed::Unknown) => write!(fmt, "parser error: requires more data"),
+            ErrorKind::CrcMismatch { expected, computed } => write!(
+                fmt,
+                "crc mismatch: expected {:#06x} but computed {:#06x}, file is likely truncated or corrupt",
+                expected, computed
+            ),
         }
     }
 }
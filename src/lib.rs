@@ -23,20 +23,52 @@
 //! }
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
+//!
+//! Records can also be written back out to a FIT data stream with [`to_writer`]:
+//! ```no_run
+//! # use fitparser;
+//! # use std::fs::File;
+//! let mut fp = File::open("tests/fixtures/Activity.fit")?;
+//! let records = fitparser::from_reader(&mut fp)?;
+//! let mut out = File::create("copy.fit")?;
+//! fitparser::to_writer(&records, &mut out)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Consumers that would rather not match on field numbers and [`Value`] variants by hand can opt
+//! into the generated, strongly-typed structs in [`messages`] instead.
+//!
+//! Large files (daily monitoring files routinely hold thousands of records) don't need to be
+//! fully materialized up front; [`Deserializer::records`] streams one record at a time instead:
+//! ```no_run
+//! # use fitparser::Deserializer;
+//! # use std::fs::File;
+//! let fp = File::open("tests/fixtures/MonitoringFile.fit")?;
+//! for record in Deserializer::new().records(fp) {
+//!     let record = record?;
+//!     println!("{:#?}", record);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 #![warn(missing_docs)]
-use chrono::{DateTime, Local};
+use chrono::{DateTime, FixedOffset};
 use serde::Serialize;
 use std::convert;
 use std::fmt;
 
 mod de;
 mod error;
+pub mod messages;
+mod options;
 pub mod profile;
 pub mod ser;
+mod write;
 
-pub use de::{from_bytes, from_reader, Deserializer};
+pub use de::{from_bytes, from_reader, from_reader_with_options, Deserializer, FileHeader, RecordIter};
 pub use error::{Error, ErrorKind, Result};
+pub use options::{DeserializeOptions, InvalidFieldPolicy, TimestampTimezone};
 use ser::{FitDataRecordSerializer, ValueWithUnits};
+pub use write::to_writer;
 
 /// Defines a set of data derived from a FIT Data message.
 #[derive(Clone, Debug, Serialize)]
@@ -179,8 +211,9 @@ impl fmt::Display for FitDataField {
 #[derive(Clone, Debug, PartialEq, PartialOrd, Serialize)]
 #[serde(untagged)]
 pub enum Value {
-    /// Timestamp field converted to the local timezone
-    Timestamp(DateTime<Local>),
+    /// Timestamp field, decoded as UTC by default; see [`TimestampTimezone`] to opt into the
+    /// local timezone instead.
+    Timestamp(DateTime<FixedOffset>),
     /// Unsigned 8bit integer data
     Byte(u8),
     /// Unsigned 8bit integer that gets mapped to a FieldType enum
@@ -220,6 +253,36 @@ pub enum Value {
     Array(Vec<Self>),
 }
 
+impl Value {
+    /// Check whether this value holds the FIT "invalid" sentinel for its base type, e.g. `0xFF`
+    /// for an unsigned byte, `0x7FFFFFFF` for a signed 32bit integer, or `0x0` for the `z`
+    /// variants whose sentinel is zero instead of all-ones. This lets consumers (and the writer)
+    /// tell an absent measurement apart from a genuine zero/max value.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Value::Timestamp(_) => true,
+            Value::Byte(val) => *val != 0xFF,
+            Value::Enum(val) => *val != 0xFF,
+            Value::SInt8(val) => *val != 0x7F,
+            Value::UInt8(val) => *val != 0xFF,
+            Value::UInt8z(val) => *val != 0x00,
+            Value::SInt16(val) => *val != 0x7FFF,
+            Value::UInt16(val) => *val != 0xFFFF,
+            Value::UInt16z(val) => *val != 0x0000,
+            Value::SInt32(val) => *val != 0x7FFF_FFFF,
+            Value::UInt32(val) => *val != 0xFFFF_FFFF,
+            Value::UInt32z(val) => *val != 0x0000_0000,
+            Value::SInt64(val) => *val != 0x7FFF_FFFF_FFFF_FFFF,
+            Value::UInt64(val) => *val != 0xFFFF_FFFF_FFFF_FFFF,
+            Value::UInt64z(val) => *val != 0x0000_0000_0000_0000,
+            Value::Float32(val) => val.to_bits() != 0xFFFF_FFFF,
+            Value::Float64(val) => val.to_bits() != 0xFFFF_FFFF_FFFF_FFFF,
+            Value::String(val) => !val.is_empty(),
+            Value::Array(vals) => vals.iter().any(Value::is_valid),
+        }
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
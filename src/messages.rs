@@ -0,0 +1,21 @@
+//! Strongly-typed per-message structs, so consumers don't have to match on field numbers or
+//! [`crate::Value`] variants by hand. They are generated from the hand-kept message and field
+//! table in `build/codegen.rs` (see also `src/bin/fitgen.rs` for the stand-alone generator),
+//! not read from the FIT profile itself, so only the messages and fields listed there are covered.
+//!
+//! Opt into typed access with [`std::convert::TryFrom`]:
+//! ```no_run
+//! # use fitparser::messages::Record;
+//! # use std::convert::TryInto;
+//! # fn example(raw: fitparser::FitDataRecord) -> fitparser::Result<()> {
+//! let record: Record = raw.try_into()?;
+//! println!("{:?}", record.heart_rate);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A field whose doc says it's "scaled by `crate::profile`" (e.g. [`Record::altitude`]) converts
+//! through [`std::convert::TryInto<f64>`] rather than matching only [`crate::Value::Float64`], so
+//! it populates whether or not [`crate::DeserializeOptions::apply_profile_scaling`] was used —
+//! just with an unscaled raw value if it wasn't.
+include!(concat!(env!("OUT_DIR"), "/messages.rs"));
@@ -0,0 +1,131 @@
+//! Deserialize options: a builder for the timezone, size limits, and other representation
+//! choices a [`crate::Deserializer`] decodes with, kept separate from the parser itself so new
+//! options don't change its call sites.
+use chrono::{FixedOffset, Local, Offset, TimeZone};
+
+/// Selects which offset parsed `Value::Timestamp` fields are expressed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimestampTimezone {
+    /// Express timestamps with a fixed `+00:00` offset. Deterministic across machines and runs;
+    /// the default.
+    Utc,
+    /// Express timestamps using this machine's local UTC offset, resolved for each timestamp's
+    /// own instant (so a DST transition between records is reflected correctly). Matches this
+    /// crate's historical behavior of decoding into `DateTime<Local>`.
+    Local,
+}
+
+impl TimestampTimezone {
+    /// Resolve the offset to express `unix_timestamp` (seconds since the Unix epoch) in. Takes
+    /// the instant being decoded, not the wall-clock time of the parse call, so `Local` resolves
+    /// each timestamp's own offset instead of stamping every record with whatever offset happens
+    /// to be in effect right now.
+    pub(crate) fn offset(self, unix_timestamp: i64) -> FixedOffset {
+        match self {
+            TimestampTimezone::Utc => FixedOffset::east(0),
+            TimestampTimezone::Local => Local.timestamp(unix_timestamp, 0).offset().fix(),
+        }
+    }
+}
+
+/// How to handle a field whose raw value is the FIT "invalid" sentinel (see [`crate::Value::is_valid`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidFieldPolicy {
+    /// Keep the field, sentinel value and all, for the caller to check with `Value::is_valid`.
+    Keep,
+    /// Drop the field entirely instead of emitting it with an invalid sentinel value.
+    Drop,
+}
+
+/// Options controlling how [`crate::Deserializer`] decodes a FIT data stream.
+///
+/// Constructed with [`DeserializeOptions::new`] (equivalent to [`Default::default`]) and
+/// configured with the builder methods below, then passed to
+/// [`crate::de::from_reader_with_options`] or [`crate::Deserializer::options`].
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeOptions {
+    pub(crate) timestamp_timezone: TimestampTimezone,
+    pub(crate) max_data_size: u32,
+    pub(crate) invalid_field_policy: InvalidFieldPolicy,
+    pub(crate) apply_profile_scaling: bool,
+    pub(crate) verify_crc: bool,
+}
+
+impl Default for DeserializeOptions {
+    fn default() -> Self {
+        DeserializeOptions {
+            timestamp_timezone: TimestampTimezone::Utc,
+            max_data_size: 64 * 1024 * 1024,
+            invalid_field_policy: InvalidFieldPolicy::Keep,
+            apply_profile_scaling: false,
+            verify_crc: false,
+        }
+    }
+}
+
+impl DeserializeOptions {
+    /// Create a new options builder with the crate's defaults: UTC timestamps, a 64MiB cap on a
+    /// single segment's declared data size, and invalid-sentinel fields kept as-is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Choose the timezone offset `Value::Timestamp` fields are decoded with.
+    pub fn timestamp_timezone(mut self, timezone: TimestampTimezone) -> Self {
+        self.timestamp_timezone = timezone;
+        self
+    }
+
+    /// Reject a segment whose header `data_size` declares more bytes than `max_bytes`, before
+    /// allocating a buffer for it. Guards against a hostile or corrupt file claiming an
+    /// enormous data section.
+    pub fn max_data_size(mut self, max_bytes: u32) -> Self {
+        self.max_data_size = max_bytes;
+        self
+    }
+
+    /// Choose whether fields holding the FIT invalid sentinel are kept or dropped.
+    pub fn invalid_field_policy(mut self, policy: InvalidFieldPolicy) -> Self {
+        self.invalid_field_policy = policy;
+        self
+    }
+
+    /// Apply the FIT profile's per-field scale/offset (decoding the physical value as
+    /// `Value::Float64`) and expand bit-packed component fields into their sub-fields. Off by
+    /// default so the historical raw-value behavior is preserved unless opted into.
+    pub fn apply_profile_scaling(mut self, apply: bool) -> Self {
+        self.apply_profile_scaling = apply;
+        self
+    }
+
+    /// Verify the header and trailing body CRC-16 of every segment, returning
+    /// [`ErrorKind::CrcMismatch`](crate::ErrorKind::CrcMismatch) instead of silently accepting a
+    /// truncated or corrupt file. Off by default. This is the knob [`crate::from_reader_with_options`]
+    /// and [`crate::from_reader`] read; [`crate::Deserializer::verify_crc`] is the equivalent for
+    /// callers driving a [`crate::Deserializer`] directly.
+    pub fn verify_crc(mut self, verify: bool) -> Self {
+        self.verify_crc = verify;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_is_always_a_zero_offset() {
+        assert_eq!(TimestampTimezone::Utc.offset(0), FixedOffset::east(0));
+        assert_eq!(TimestampTimezone::Utc.offset(1_700_000_000), FixedOffset::east(0));
+    }
+
+    #[test]
+    fn local_resolves_the_offset_for_the_instant_being_decoded_not_the_wall_clock() {
+        // Two timestamps a year apart must each resolve their own offset rather than both being
+        // stamped with whatever offset happens to be in effect when `offset()` is called.
+        let a = 0i64;
+        let b = 365 * 24 * 60 * 60;
+        assert_eq!(TimestampTimezone::Local.offset(a), Local.timestamp(a, 0).offset().fix());
+        assert_eq!(TimestampTimezone::Local.offset(b), Local.timestamp(b, 0).offset().fix());
+    }
+}
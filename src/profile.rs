@@ -0,0 +1,343 @@
+//! FIT profile identifiers defined by the Garmin FIT SDK.
+//!
+//! Only the `MesgNum` values exercised by this crate's test fixtures are enumerated by name;
+//! anything else round-trips through [`MesgNum::Unknown`] so no information is lost.
+use std::fmt;
+
+use serde::Serialize;
+
+/// Identifies which FIT message a [`crate::FitDataRecord`] was decoded from, as defined by the
+/// FIT profile's `mesg_num` type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum MesgNum {
+    /// `file_id` (0): identifies the type, manufacturer, and product of a FIT file.
+    FileId,
+    /// `session` (18): summary of a single activity session.
+    Session,
+    /// `lap` (19): summary of a single lap within a session.
+    Lap,
+    /// `record` (20): a single timestamped sample (position, heart rate, power, ...).
+    Record,
+    /// `event` (21): a timestamped start/stop/marker event.
+    Event,
+    /// `device_info` (23): identifies a connected sensor or peripheral.
+    DeviceInfo,
+    /// `workout` (26): a structured workout definition.
+    Workout,
+    /// `workout_step` (27): a single step within a structured workout.
+    WorkoutStep,
+    /// `weight_scale` (30): a single weight scale measurement.
+    WeightScale,
+    /// `activity` (34): top level summary of a recorded activity.
+    Activity,
+    /// `file_creator` (49): software version that produced the file.
+    FileCreator,
+    /// `monitoring` (55): a single daily activity monitoring sample.
+    Monitoring,
+    /// `monitoring_info` (103): metadata describing a monitoring file.
+    MonitoringInfo,
+    /// `field_description` (206): describes a developer-defined field.
+    FieldDescription,
+    /// `developer_data_id` (207): identifies the application that owns developer fields.
+    DeveloperDataId,
+    /// A message number not (yet) enumerated by name.
+    Unknown(u16),
+}
+
+impl MesgNum {
+    /// Return the raw FIT global message number for this `MesgNum`.
+    pub fn as_u16(self) -> u16 {
+        match self {
+            MesgNum::FileId => 0,
+            MesgNum::Session => 18,
+            MesgNum::Lap => 19,
+            MesgNum::Record => 20,
+            MesgNum::Event => 21,
+            MesgNum::DeviceInfo => 23,
+            MesgNum::Workout => 26,
+            MesgNum::WorkoutStep => 27,
+            MesgNum::WeightScale => 30,
+            MesgNum::Activity => 34,
+            MesgNum::FileCreator => 49,
+            MesgNum::Monitoring => 55,
+            MesgNum::MonitoringInfo => 103,
+            MesgNum::FieldDescription => 206,
+            MesgNum::DeveloperDataId => 207,
+            MesgNum::Unknown(num) => num,
+        }
+    }
+}
+
+impl From<u16> for MesgNum {
+    fn from(num: u16) -> Self {
+        match num {
+            0 => MesgNum::FileId,
+            18 => MesgNum::Session,
+            19 => MesgNum::Lap,
+            20 => MesgNum::Record,
+            21 => MesgNum::Event,
+            23 => MesgNum::DeviceInfo,
+            26 => MesgNum::Workout,
+            27 => MesgNum::WorkoutStep,
+            30 => MesgNum::WeightScale,
+            34 => MesgNum::Activity,
+            49 => MesgNum::FileCreator,
+            55 => MesgNum::Monitoring,
+            103 => MesgNum::MonitoringInfo,
+            206 => MesgNum::FieldDescription,
+            207 => MesgNum::DeveloperDataId,
+            num => MesgNum::Unknown(num),
+        }
+    }
+}
+
+impl fmt::Display for MesgNum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MesgNum::FileId => write!(f, "file_id"),
+            MesgNum::Session => write!(f, "session"),
+            MesgNum::Lap => write!(f, "lap"),
+            MesgNum::Record => write!(f, "record"),
+            MesgNum::Event => write!(f, "event"),
+            MesgNum::DeviceInfo => write!(f, "device_info"),
+            MesgNum::Workout => write!(f, "workout"),
+            MesgNum::WorkoutStep => write!(f, "workout_step"),
+            MesgNum::WeightScale => write!(f, "weight_scale"),
+            MesgNum::Activity => write!(f, "activity"),
+            MesgNum::FileCreator => write!(f, "file_creator"),
+            MesgNum::Monitoring => write!(f, "monitoring"),
+            MesgNum::MonitoringInfo => write!(f, "monitoring_info"),
+            MesgNum::FieldDescription => write!(f, "field_description"),
+            MesgNum::DeveloperDataId => write!(f, "developer_data_id"),
+            MesgNum::Unknown(num) => write!(f, "unknown_{}", num),
+        }
+    }
+}
+
+/// A component sub-field packed into a parent field's raw integer, e.g. the speed half of
+/// `record.compressed_speed_distance`. Components are sliced out LSB-first and decoded with
+/// their own scale/offset, independent of the parent field's.
+pub(crate) struct ComponentField {
+    /// Field number the expanded component should be emitted under, matching the number its
+    /// standalone counterpart (e.g. `record.speed`) would use.
+    pub number: u8,
+    pub name: &'static str,
+    pub units: &'static str,
+    /// Width, in bits, of this component within the parent's packed raw integer.
+    pub bits: u32,
+    pub scale: Option<f64>,
+    pub offset: Option<f64>,
+}
+
+/// Scale/offset and optional bit-packed component layout for a single profile field, as defined
+/// by the FIT SDK's `Messages.xlsx`/profile CSV. Only the fields exercised by this crate's test
+/// fixtures are included; anything else decodes as a raw, unscaled `Value`.
+pub(crate) struct FieldProfile {
+    pub name: &'static str,
+    pub units: &'static str,
+    pub scale: Option<f64>,
+    pub offset: Option<f64>,
+    pub components: &'static [ComponentField],
+}
+
+const RECORD_DISTANCE: FieldProfile = FieldProfile {
+    name: "distance",
+    units: "m",
+    scale: Some(100.0),
+    offset: None,
+    components: &[],
+};
+
+const RECORD_SPEED: FieldProfile = FieldProfile {
+    name: "speed",
+    units: "m/s",
+    scale: Some(1000.0),
+    offset: None,
+    components: &[],
+};
+
+const RECORD_ALTITUDE: FieldProfile = FieldProfile {
+    name: "altitude",
+    units: "m",
+    scale: Some(5.0),
+    offset: Some(500.0),
+    components: &[],
+};
+
+/// `record.compressed_speed_distance` (field 8): a 24bit value packing 12 bits of speed and 12
+/// bits of distance, each with their own scale, so the two can travel in a smaller message.
+const RECORD_COMPRESSED_SPEED_DISTANCE: FieldProfile = FieldProfile {
+    name: "compressed_speed_distance",
+    units: "",
+    scale: None,
+    offset: None,
+    components: &[
+        ComponentField {
+            number: 6, // record.speed
+            name: "speed",
+            units: "m/s",
+            bits: 12,
+            scale: Some(100.0),
+            offset: None,
+        },
+        ComponentField {
+            number: 5, // record.distance
+            name: "distance",
+            units: "m",
+            bits: 12,
+            scale: Some(16.0),
+            offset: None,
+        },
+    ],
+};
+
+/// Look up the scale/offset/component layout for a field, if this crate knows about it.
+pub(crate) fn field_profile(mesg: MesgNum, field_number: u8) -> Option<&'static FieldProfile> {
+    match (mesg, field_number) {
+        (MesgNum::Record, 5) => Some(&RECORD_DISTANCE),
+        (MesgNum::Record, 6) => Some(&RECORD_SPEED),
+        (MesgNum::Record, 2) => Some(&RECORD_ALTITUDE),
+        (MesgNum::Record, 8) => Some(&RECORD_COMPRESSED_SPEED_DISTANCE),
+        _ => None,
+    }
+}
+
+fn physical_value(raw: &crate::Value, scale: Option<f64>, offset: Option<f64>) -> crate::Value {
+    use std::convert::TryInto;
+    match raw.clone().try_into() as crate::Result<f64> {
+        Ok(raw) => crate::Value::Float64(raw / scale.unwrap_or(1.0) - offset.unwrap_or(0.0)),
+        Err(_) => raw.clone(),
+    }
+}
+
+/// Slice `raw` into its component sub-fields (LSB-first) and apply each component's own
+/// scale/offset, returning `(field_number, name, value, units)` for each.
+fn expand_components(
+    raw: &crate::Value,
+    components: &'static [ComponentField],
+) -> Vec<(u8, &'static str, crate::Value, &'static str)> {
+    use std::convert::TryInto;
+    let packed: i64 = match raw {
+        // A packed multi-byte field such as the 3-byte `compressed_speed_distance` decodes as an
+        // array of single bytes; reassemble it little-endian into one integer.
+        crate::Value::Array(bytes) => bytes.iter().take(8).enumerate().fold(0i64, |packed, (i, byte)| {
+            let byte: i64 = byte.clone().try_into().unwrap_or(0);
+            packed | ((byte & 0xFF) << (8 * i))
+        }),
+        other => match other.clone().try_into() {
+            Ok(packed) => packed,
+            Err(_) => return Vec::new(),
+        },
+    };
+    let mut shift = 0u32;
+    let mut expanded = Vec::with_capacity(components.len());
+    for component in components {
+        let mask = (1i64 << component.bits) - 1;
+        let component_raw = (packed >> shift) & mask;
+        shift += component.bits;
+        let raw_value = crate::Value::UInt32(component_raw as u32);
+        // All bits set is this component's own invalid sentinel within its packed width (the
+        // same convention a standalone field of that width would use); leave it unscaled so it
+        // propagates as invalid instead of being divided into a fabricated physical value.
+        let value = if component_raw == mask {
+            raw_value
+        } else {
+            physical_value(&raw_value, component.scale, component.offset)
+        };
+        expanded.push((component.number, component.name, value, component.units));
+    }
+    expanded
+}
+
+/// Apply this field's profile-defined scale/offset, or expand it into its component sub-fields,
+/// returning the `(field_number, name, value, units)` tuples that should replace the raw field.
+/// Invalid raw values are passed through unscaled so callers can still detect them with
+/// [`crate::Value::is_valid`]. Fields with no known profile entry are returned unchanged, named
+/// generically.
+pub(crate) fn decode(
+    mesg: MesgNum,
+    field_number: u8,
+    raw: crate::Value,
+) -> Vec<(u8, String, crate::Value, String)> {
+    let profile = match field_profile(mesg, field_number) {
+        Some(profile) => profile,
+        None => return vec![(field_number, format!("field_{}", field_number), raw, String::new())],
+    };
+
+    if !raw.is_valid() {
+        return vec![(field_number, profile.name.to_string(), raw, profile.units.to_string())];
+    }
+
+    if !profile.components.is_empty() {
+        return expand_components(&raw, profile.components)
+            .into_iter()
+            .map(|(number, name, value, units)| (number, name.to_string(), value, units.to_string()))
+            .collect();
+    }
+
+    let value = physical_value(&raw, profile.scale, profile.offset);
+    vec![(field_number, profile.name.to_string(), value, profile.units.to_string())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_raw_message_number() {
+        for mesg_num in [
+            MesgNum::FileId,
+            MesgNum::Record,
+            MesgNum::Activity,
+            MesgNum::Unknown(9999),
+        ] {
+            assert_eq!(MesgNum::from(mesg_num.as_u16()), mesg_num);
+        }
+    }
+
+    #[test]
+    fn compressed_speed_distance_expands_into_two_components() {
+        let profile = field_profile(MesgNum::Record, 8).unwrap();
+        assert_eq!(profile.components.len(), 2);
+        assert_eq!(profile.components[0].bits + profile.components[1].bits, 24);
+    }
+
+    #[test]
+    fn unknown_fields_have_no_profile() {
+        assert!(field_profile(MesgNum::Record, 250).is_none());
+    }
+
+    #[test]
+    fn scales_a_plain_field() {
+        let decoded = decode(MesgNum::Record, 2, crate::Value::UInt16(2500));
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].1, "altitude");
+        assert_eq!(decoded[0].2, crate::Value::Float64(2500.0 / 5.0 - 500.0));
+    }
+
+    #[test]
+    fn expands_compressed_speed_distance() {
+        // speed = 100 (raw units of 1/100 m/s), distance = 16 (raw units of 1/16 m), packed LSB-first
+        let packed = 100u32 | (16u32 << 12);
+        let decoded = decode(MesgNum::Record, 8, crate::Value::UInt32(packed));
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0], (6, "speed".to_string(), crate::Value::Float64(1.0), "m/s".to_string()));
+        assert_eq!(decoded[1], (5, "distance".to_string(), crate::Value::Float64(1.0), "m".to_string()));
+    }
+
+    #[test]
+    fn leaves_an_invalid_component_unscaled_while_its_sibling_still_scales() {
+        // speed's 12 bits are all set (its own invalid sentinel); distance is a valid 16.
+        let packed = 0xFFFu32 | (16u32 << 12);
+        let decoded = decode(MesgNum::Record, 8, crate::Value::UInt32(packed));
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0], (6, "speed".to_string(), crate::Value::UInt32(0xFFF), "m/s".to_string()));
+        assert_eq!(decoded[1], (5, "distance".to_string(), crate::Value::Float64(1.0), "m".to_string()));
+    }
+
+    #[test]
+    fn leaves_invalid_raw_values_unscaled() {
+        let decoded = decode(MesgNum::Record, 2, crate::Value::UInt16(0xFFFF));
+        assert_eq!(decoded[0].2, crate::Value::UInt16(0xFFFF));
+    }
+}
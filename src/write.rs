@@ -0,0 +1,279 @@
+//! Encodes [`FitDataRecord`]s back into the FIT binary format.
+//!
+//! This is the write-side counterpart to the parser: it does not attempt to support every
+//! message layout the FIT profile can describe, only enough of the definition/data message
+//! framing to faithfully round-trip data produced by this crate (parse -> write -> parse should
+//! yield identical records).
+use std::convert::TryFrom;
+use std::io::Write;
+
+use crate::{profile::MesgNum, Error, ErrorKind, FitDataRecord, Result, Value};
+
+const HEADER_SIZE: u8 = 14;
+const FIT_PROTOCOL_VERSION: u8 = 0x10;
+const FIT_PROFILE_VERSION: u16 = 2167;
+const FIT_TAG: [u8; 4] = *b".FIT";
+/// Number of seconds between the Unix epoch and the FIT epoch (1989-12-31T00:00:00Z).
+const FIT_EPOCH_OFFSET: i64 = 631_065_600;
+
+/// Local message type used for every definition/data message. A single slot is sufficient since
+/// we always (re-)emit a definition message immediately before the data messages it describes.
+const LOCAL_MESG_NUM: u8 = 0;
+
+const CRC_TABLE: [u16; 16] = [
+    0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800,
+    0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+];
+
+/// Fold one more byte into a running FIT CRC-16. Exposed to `de` so the streaming record
+/// iterator can verify the trailing CRC without buffering an entire segment's body first.
+pub(crate) fn crc_update(crc: u16, byte: u8) -> u16 {
+    let step = |crc: u16, nibble: u8| -> u16 {
+        let tmp = CRC_TABLE[(crc & 0xF) as usize];
+        let crc = (crc >> 4) & 0x0FFF;
+        crc ^ tmp ^ CRC_TABLE[(nibble & 0xF) as usize]
+    };
+    step(step(crc, byte), byte >> 4)
+}
+
+/// Compute the FIT CRC-16 over a byte slice, starting from a zero seed.
+pub(crate) fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |crc, &byte| crc_update(crc, byte))
+}
+
+/// Write `records` to `w` as a single FIT data stream: file header, definition/data messages,
+/// and the trailing CRC-16.
+///
+/// Records are expected to be grouped so that consecutive records sharing the same message kind
+/// and field set only need one definition message between them; a new definition message is
+/// emitted whenever that signature changes. The signature includes each field's encoded size (not
+/// just its number), so two records that share field numbers but differ in a variable-width
+/// value's length (a `String`/`Array` field) still get their own definition message instead of
+/// desynchronizing the body against a stale one.
+pub fn to_writer<W: Write>(records: &[FitDataRecord], w: &mut W) -> Result<()> {
+    let mut body = Vec::new();
+    let mut last_signature: Option<(MesgNum, Vec<(u8, u8)>)> = None;
+
+    for record in records {
+        let signature = field_signature(record)?;
+        if last_signature.as_ref() != Some(&signature) {
+            write_definition_message(&mut body, record)?;
+            last_signature = Some(signature);
+        }
+        write_data_message(&mut body, record)?;
+    }
+
+    let header = build_header(body.len() as u32);
+    w.write_all(&header)?;
+    w.write_all(&body)?;
+
+    let file_crc = header
+        .iter()
+        .chain(body.iter())
+        .fold(0u16, |crc, &byte| crc_update(crc, byte));
+    w.write_all(&file_crc.to_le_bytes())?;
+    Ok(())
+}
+
+fn build_header(data_size: u32) -> [u8; HEADER_SIZE as usize] {
+    let mut header = [0u8; HEADER_SIZE as usize];
+    header[0] = HEADER_SIZE;
+    header[1] = FIT_PROTOCOL_VERSION;
+    header[2..4].copy_from_slice(&FIT_PROFILE_VERSION.to_le_bytes());
+    header[4..8].copy_from_slice(&data_size.to_le_bytes());
+    header[8..12].copy_from_slice(&FIT_TAG);
+    let header_crc = crc16(&header[0..12]);
+    header[12..14].copy_from_slice(&header_crc.to_le_bytes());
+    header
+}
+
+/// The `(field_number, encoded_size)` pairs a definition message for `record` would declare,
+/// used to decide whether a previously-emitted definition message can still be reused for it.
+fn field_signature(record: &FitDataRecord) -> Result<(MesgNum, Vec<(u8, u8)>)> {
+    let fields = record
+        .fields()
+        .iter()
+        .map(|field| Ok((field.number(), base_type_of(field.value())?.1)))
+        .collect::<Result<Vec<(u8, u8)>>>()?;
+    Ok((record.kind(), fields))
+}
+
+fn write_definition_message(out: &mut Vec<u8>, record: &FitDataRecord) -> Result<()> {
+    out.push(0x40 | LOCAL_MESG_NUM);
+    out.push(0); // reserved
+    out.push(0); // architecture: little-endian
+    out.extend_from_slice(&record.kind().as_u16().to_le_bytes());
+    out.push(record.fields().len() as u8);
+    for field in record.fields() {
+        let (base_type, size) = base_type_of(field.value())?;
+        out.push(field.number());
+        out.push(size);
+        out.push(base_type);
+    }
+    Ok(())
+}
+
+fn write_data_message(out: &mut Vec<u8>, record: &FitDataRecord) -> Result<()> {
+    out.push(LOCAL_MESG_NUM);
+    for field in record.fields() {
+        write_value(out, field.value())?;
+    }
+    Ok(())
+}
+
+/// Return the FIT base type byte and encoded size (in bytes) for a `Value`.
+fn base_type_of(value: &Value) -> Result<(u8, u8)> {
+    Ok(match value {
+        Value::Timestamp(_) => (0x86, 4),
+        Value::Enum(_) => (0x00, 1),
+        Value::Byte(_) => (0x0D, 1),
+        Value::SInt8(_) => (0x01, 1),
+        Value::UInt8(_) => (0x02, 1),
+        Value::UInt8z(_) => (0x0A, 1),
+        Value::SInt16(_) => (0x83, 2),
+        Value::UInt16(_) => (0x84, 2),
+        Value::UInt16z(_) => (0x8B, 2),
+        Value::SInt32(_) => (0x85, 4),
+        Value::UInt32(_) => (0x86, 4),
+        Value::UInt32z(_) => (0x8C, 4),
+        Value::SInt64(_) => (0x8E, 8),
+        Value::UInt64(_) => (0x8F, 8),
+        Value::UInt64z(_) => (0x90, 8),
+        Value::Float32(_) => (0x88, 4),
+        Value::Float64(_) => (0x89, 8),
+        Value::String(s) => {
+            let encoded_len = s.len() + 1; // + the trailing nul
+            let size = u8::try_from(encoded_len).map_err(|_| -> Error {
+                ErrorKind::Custom(format!(
+                    "string field value is {} bytes, too long to encode in a definition message (max {})",
+                    s.len(),
+                    u8::MAX as usize - 1
+                ))
+                .into()
+            })?;
+            (0x07, size)
+        }
+        Value::Array(vals) => {
+            let first = vals.first().ok_or_else(|| -> Error {
+                ErrorKind::Custom("cannot encode an empty array field".to_string()).into()
+            })?;
+            let (base_type, elem_size) = base_type_of(first)?;
+            let encoded_len = elem_size as usize * vals.len();
+            let size = u8::try_from(encoded_len).map_err(|_| -> Error {
+                ErrorKind::Custom(format!(
+                    "array field encodes to {} bytes, too long to fit in a definition message (max 255)",
+                    encoded_len
+                ))
+                .into()
+            })?;
+            (base_type, size)
+        }
+    })
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) -> Result<()> {
+    match value {
+        Value::Timestamp(val) => {
+            let fit_epoch_secs = (val.timestamp() - FIT_EPOCH_OFFSET) as u32;
+            out.extend_from_slice(&fit_epoch_secs.to_le_bytes());
+        }
+        Value::Enum(val) | Value::Byte(val) | Value::UInt8(val) | Value::UInt8z(val) => {
+            out.push(*val)
+        }
+        Value::SInt8(val) => out.push(*val as u8),
+        Value::SInt16(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::UInt16(val) | Value::UInt16z(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::SInt32(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::UInt32(val) | Value::UInt32z(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::SInt64(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::UInt64(val) | Value::UInt64z(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::Float32(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::Float64(val) => out.extend_from_slice(&val.to_le_bytes()),
+        Value::String(s) => {
+            out.extend_from_slice(s.as_bytes());
+            out.push(0);
+        }
+        Value::Array(vals) => {
+            for val in vals {
+                write_value(out, val)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FitDataField;
+
+    #[test]
+    fn header_is_fourteen_bytes_and_self_describing() {
+        let header = build_header(42);
+        assert_eq!(header.len(), HEADER_SIZE as usize);
+        assert_eq!(header[0], HEADER_SIZE);
+        assert_eq!(&header[4..8], &42u32.to_le_bytes());
+        assert_eq!(&header[8..12], b".FIT");
+        let header_crc = u16::from_le_bytes([header[12], header[13]]);
+        assert_eq!(header_crc, crc16(&header[0..12]));
+    }
+
+    #[test]
+    fn crc16_is_order_sensitive_and_deterministic() {
+        let forward = crc16(b"fitparser");
+        let reversed = crc16(b"resrapitf");
+        assert_eq!(forward, crc16(b"fitparser"));
+        assert_ne!(forward, reversed);
+    }
+
+    #[test]
+    fn encodes_fields_to_their_native_width() {
+        let mut out = Vec::new();
+        write_value(&mut out, &Value::UInt16(1234)).unwrap();
+        assert_eq!(out, 1234u16.to_le_bytes());
+
+        out.clear();
+        write_value(&mut out, &Value::String("abc".to_string())).unwrap();
+        assert_eq!(out, vec![b'a', b'b', b'c', 0]);
+    }
+
+    #[test]
+    fn rejects_a_string_field_too_long_to_declare_in_a_definition_message() {
+        let value = Value::String("x".repeat(255));
+        assert!(base_type_of(&value).is_err());
+    }
+
+    #[test]
+    fn rejects_an_array_field_whose_encoded_size_overflows_a_byte() {
+        let value = Value::Array(vec![Value::UInt32(0); 64]);
+        assert!(base_type_of(&value).is_err());
+    }
+
+    #[test]
+    fn emits_a_new_definition_when_a_string_fields_length_changes() {
+        // Same message kind and field number in both records, but the second record's string is
+        // longer - reusing the first record's definition message would desync the body from here.
+        let mut short = FitDataRecord::new(MesgNum::FileId);
+        short.push(FitDataField::new(
+            "product_name".to_string(),
+            8,
+            Value::String("abc".to_string()),
+            String::new(),
+        ));
+        let mut long = FitDataRecord::new(MesgNum::FileId);
+        long.push(FitDataField::new(
+            "product_name".to_string(),
+            8,
+            Value::String("abcdefgh".to_string()),
+            String::new(),
+        ));
+
+        let mut buf = Vec::new();
+        to_writer(&[short, long], &mut buf).unwrap();
+
+        let parsed = crate::from_bytes(&buf).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].fields()[0].value(), &Value::String("abc".to_string()));
+        assert_eq!(parsed[1].fields()[0].value(), &Value::String("abcdefgh".to_string()));
+    }
+}